@@ -0,0 +1,73 @@
+
+//! Reading and writing the compressed pixel data blocks that make up the body of a file.
+
+use crate::meta::{Header, Limits};
+use crate::error::{Result, Error};
+
+/// A single decompressed block of scan lines or a single decompressed tile.
+#[derive(Debug, Clone)]
+pub struct UncompressedBlock {
+    /// The raw, decompressed pixel bytes belonging to this block.
+    pub data: Vec<u8>,
+}
+
+/// Tracks how many bytes have been allocated for decompressed blocks so far,
+/// so that [`decompress_block`] can enforce [`Limits::max_total_bytes`] across
+/// every layer and block of a file, not just a single block in isolation.
+#[derive(Debug, Default)]
+pub struct AllocationBudget {
+    allocated_bytes: usize,
+}
+
+/// Decompress one block of a layer, validating the header-declared sizes
+/// against `limits`, and checking `budget` against [`Limits::max_total_bytes`],
+/// before allocating anything for the decompressed result.
+pub fn decompress_block(
+    header: &Header, compressed: &[u8], limits: &Limits, budget: &mut AllocationBudget,
+) -> Result<UncompressedBlock> {
+    header.validate_against_limits(limits)?;
+
+    let block_pixels = header.tile_size.unwrap_or(header.layer_size).checked_area()
+        .ok_or_else(|| Error::invalid("block size overflows"))?;
+
+    let expected_byte_size = block_pixels.checked_mul(header.channels.bytes_per_pixel())
+        .ok_or_else(|| Error::invalid("block size overflows"))?;
+
+    if expected_byte_size > limits.max_tile_bytes {
+        return Err(Error::invalid("block size exceeds the configured limit"));
+    }
+
+    let total_after_block = budget.allocated_bytes.checked_add(expected_byte_size)
+        .filter(|total| *total <= limits.max_total_bytes)
+        .ok_or_else(|| Error::invalid("total allocation across all layers exceeds the configured limit"))?;
+
+    let data = header.compression.decompress(compressed, expected_byte_size)?;
+    budget.allocated_bytes = total_after_block;
+
+    Ok(UncompressedBlock { data })
+}
+
+/// Read one compressed block out of `bytes` at `offset`: an 8-byte little-endian
+/// byte count, followed by that many bytes of compressed data.
+pub(crate) fn read_chunk(bytes: &[u8], offset: usize) -> Result<&[u8]> {
+    let data_start = offset.checked_add(8)
+        .ok_or_else(|| Error::invalid("file is truncated"))?;
+
+    let size_bytes = bytes.get(offset .. data_start)
+        .ok_or_else(|| Error::invalid("file is truncated"))?;
+
+    let size = u64::from_le_bytes(size_bytes.try_into().unwrap()) as usize;
+
+    let data_end = data_start.checked_add(size)
+        .ok_or_else(|| Error::invalid("file is truncated"))?;
+
+    bytes.get(data_start .. data_end)
+        .ok_or_else(|| Error::invalid("file is truncated"))
+}
+
+/// Append one compressed block to `bytes`, prefixed with its byte count, in the
+/// format [`read_chunk`] expects.
+pub(crate) fn write_chunk(bytes: &mut Vec<u8>, compressed: &[u8]) {
+    bytes.extend_from_slice(&(compressed.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(compressed);
+}
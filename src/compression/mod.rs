@@ -0,0 +1,77 @@
+
+//! Compression and decompression of pixel data blocks.
+
+mod zip;
+mod rle;
+mod piz;
+
+use crate::error::{Result, Error};
+
+/// The compression method used to store a layer's pixel data.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Compression {
+    /// No compression at all.
+    Uncompressed,
+
+    /// Zlib compression, applied per scan line.
+    ZIP1,
+
+    /// Zlib compression, applied to blocks of 16 scan lines.
+    ZIP16,
+
+    /// Run-length encoding.
+    RLE,
+
+    /// Wavelet compression, lossy for the chroma channels.
+    PIZ,
+}
+
+impl Compression {
+    /// Decode the `compression` attribute's single-byte enum.
+    pub(crate) fn from_byte(byte: u8) -> Result<Self> {
+        match byte {
+            0 => Ok(Compression::Uncompressed),
+            1 => Ok(Compression::RLE),
+            2 => Ok(Compression::ZIP1),
+            3 => Ok(Compression::ZIP16),
+            4 => Ok(Compression::PIZ),
+            _ => Err(Error::unsupported("unknown compression method")),
+        }
+    }
+
+    /// Encode this compression method as the `compression` attribute's single-byte enum.
+    pub(crate) fn to_byte(self) -> u8 {
+        match self {
+            Compression::Uncompressed => 0,
+            Compression::RLE => 1,
+            Compression::ZIP1 => 2,
+            Compression::ZIP16 => 3,
+            Compression::PIZ => 4,
+        }
+    }
+
+    /// Decompress a single block of bytes, refusing to allocate more than
+    /// `expected_byte_size` bytes for the result.
+    ///
+    /// The compressed representation of a block only ever claims how large the
+    /// input is, never how large the decompressed output will be; a hostile file
+    /// can supply a tiny compressed chunk that requests an enormous expansion.
+    /// Every decompressor is therefore handed the exact uncompressed size the
+    /// block is supposed to have (computed from the header's tile size or layer
+    /// resolution and its channel list) and must stop, rather than over-allocate, the
+    /// moment it would produce more bytes than that.
+    pub fn decompress(self, compressed: &[u8], expected_byte_size: usize) -> Result<Vec<u8>> {
+        let decompressed = match self {
+            Compression::Uncompressed => compressed.to_vec(),
+            Compression::ZIP1 | Compression::ZIP16 => zip::decompress(compressed, expected_byte_size)?,
+            Compression::RLE => rle::decompress(compressed, expected_byte_size)?,
+            Compression::PIZ => piz::decompress(compressed, expected_byte_size)?,
+        };
+
+        if decompressed.len() != expected_byte_size {
+            return Err(Error::invalid("decompressed block size does not match the expected size"));
+        }
+
+        Ok(decompressed)
+    }
+}
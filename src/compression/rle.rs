@@ -0,0 +1,54 @@
+
+//! Run-length encoding, as used by the `RLE` compression method.
+
+use crate::error::{Result, Error};
+
+/// Decompress a run-length encoded block, refusing to grow the output buffer
+/// past `expected_byte_size` even if the encoded runs claim to produce more.
+pub fn decompress(compressed: &[u8], expected_byte_size: usize) -> Result<Vec<u8>> {
+    let mut decompressed = Vec::with_capacity(expected_byte_size);
+    let mut remaining = compressed;
+
+    while !remaining.is_empty() {
+        let count = remaining[0] as i8 as i32;
+        remaining = &remaining[1..];
+
+        if count < 0 {
+            // a negative count introduces `-count` literal bytes
+            let literal_count = (-count) as usize;
+            if literal_count > remaining.len() {
+                return Err(Error::invalid("rle stream is truncated"));
+            }
+
+            push_capped(&mut decompressed, &remaining[..literal_count], expected_byte_size)?;
+            remaining = &remaining[literal_count..];
+        }
+        else {
+            // a non-negative count repeats the following byte `count + 1` times
+            let run_length = count as usize + 1;
+            if remaining.is_empty() {
+                return Err(Error::invalid("rle stream is truncated"));
+            }
+
+            let repeated_byte = remaining[0];
+            remaining = &remaining[1..];
+
+            if decompressed.len() + run_length > expected_byte_size {
+                return Err(Error::invalid("rle stream decompresses larger than the expected block size"));
+            }
+
+            decompressed.resize(decompressed.len() + run_length, repeated_byte);
+        }
+    }
+
+    Ok(decompressed)
+}
+
+fn push_capped(decompressed: &mut Vec<u8>, bytes: &[u8], expected_byte_size: usize) -> Result<()> {
+    if decompressed.len() + bytes.len() > expected_byte_size {
+        return Err(Error::invalid("rle stream decompresses larger than the expected block size"));
+    }
+
+    decompressed.extend_from_slice(bytes);
+    Ok(())
+}
@@ -0,0 +1,22 @@
+
+//! Zlib-based compression, used by both `ZIP1` and `ZIP16`.
+
+use std::io::Read;
+use crate::error::{Result, Error};
+
+/// Decompress a zlib-compressed block, never producing more than `expected_byte_size`
+/// bytes of output even if the stream claims to expand further.
+pub fn decompress(compressed: &[u8], expected_byte_size: usize) -> Result<Vec<u8>> {
+    // read one byte past the expected size so an over-long stream is detected
+    // as an error instead of silently truncated
+    let mut decoder = flate2::read::ZlibDecoder::new(compressed).take(expected_byte_size as u64 + 1);
+
+    let mut decompressed = Vec::with_capacity(expected_byte_size);
+    decoder.read_to_end(&mut decompressed).map_err(|_| Error::invalid("zip stream is corrupt"))?;
+
+    if decompressed.len() > expected_byte_size {
+        return Err(Error::invalid("zip stream claims to decompress larger than the expected block size"));
+    }
+
+    Ok(decompressed)
+}
@@ -0,0 +1,59 @@
+
+//! Error and result types used throughout this crate.
+
+use std::borrow::Cow;
+use std::io;
+
+/// A result that does not carry any successful value, only success or failure.
+pub type UnitResult = Result<()>;
+
+/// The result type for all fallible operations in this crate.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// An error that can occur while reading or writing an `.exr` file.
+#[derive(Debug)]
+pub enum Error {
+    /// The file contents do not conform to the OpenEXR specification.
+    Invalid(Cow<'static, str>),
+
+    /// The file is technically valid, but uses a feature that is not supported by this crate.
+    NotSupported(Cow<'static, str>),
+
+    /// An underlying io error, for example a missing file or a broken pipe.
+    Io(io::Error),
+
+    /// The operation was aborted before it completed. The `on_progress` callbacks
+    /// cannot abort an operation, and no function of this crate returns this variant.
+    Aborted,
+}
+
+impl Error {
+    /// Create an `Error::Invalid` from a static string.
+    pub fn invalid(message: impl Into<Cow<'static, str>>) -> Self {
+        Error::Invalid(message.into())
+    }
+
+    /// Create an `Error::NotSupported` from a static string.
+    pub fn unsupported(message: impl Into<Cow<'static, str>>) -> Self {
+        Error::NotSupported(message.into())
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Invalid(message) => write!(formatter, "invalid: {}", message),
+            Error::NotSupported(message) => write!(formatter, "not supported: {}", message),
+            Error::Io(io) => write!(formatter, "io error: {}", io),
+            Error::Aborted => write!(formatter, "aborted"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,162 @@
+
+//! The in-memory representation of a decoded (or to-be-encoded) `.exr` image.
+
+pub mod read;
+pub mod write;
+
+use crate::math::Vec2;
+use crate::meta::attribute::{ChannelList, SampleType};
+use crate::image::read::rgba_channels::pixels::{Flattened, ToLinear};
+
+/// A decoded image, generic over how its layers are represented in memory.
+///
+/// Depending on how the image was read, `LayerData` may be a single RGBA layer,
+/// a list of fully general layers, or anything else produced by the `read()` builder.
+#[derive(Debug, Clone)]
+pub struct Image<LayerData> {
+    /// The layer data of this image, in whatever shape the reader was configured to produce.
+    pub layer_data: LayerData,
+}
+
+impl<LayerData> Image<LayerData> {
+    /// Wrap a single layer's data into an image, for example before writing it to disk.
+    pub fn with_single_layer(size: Vec2<usize>, channel_data: LayerData) -> Image<Layer<LayerData>> {
+        Image { layer_data: Layer { size, channel_data } }
+    }
+}
+
+/// A single layer of an image: its resolution and its channel data.
+#[derive(Debug, Clone)]
+pub struct Layer<ChannelData> {
+    /// The resolution of this layer, in pixels.
+    pub size: Vec2<usize>,
+
+    /// The pixel data of this layer.
+    pub channel_data: ChannelData,
+}
+
+/// The four (or three) channels of an RGBA layer, together with the in-memory
+/// storage that was chosen to hold their samples.
+#[derive(Debug, Clone)]
+pub struct RgbaChannels<Storage> {
+    /// The sample type used for each of the red, green, blue and optional alpha channels.
+    pub sample_types: RgbaSampleTypes,
+
+    /// The actual pixel samples, in whatever shape the reader was configured to produce.
+    pub storage: Storage,
+}
+
+impl<Storage> RgbaChannels<Storage> {
+    /// Combine a sample type description with the storage that holds the decoded samples.
+    pub fn new(sample_types: RgbaSampleTypes, storage: Storage) -> Self {
+        RgbaChannels { sample_types, storage }
+    }
+
+    /// The channel list implied by this layer's sample types, in red, green, blue, alpha order.
+    pub fn channel_list(&self) -> ChannelList {
+        let RgbaSampleTypes(r, g, b, a) = self.sample_types;
+
+        let mut list = vec![
+            channel("R", r), channel("G", g), channel("B", b),
+        ];
+
+        if let Some(a) = a {
+            list.push(channel("A", a));
+        }
+
+        ChannelList { list }
+    }
+}
+
+fn channel(name: &str, sample_type: SampleType) -> crate::meta::attribute::ChannelDescription {
+    crate::meta::attribute::ChannelDescription { name: name.to_string(), sample_type }
+}
+
+/// The sample type of each of the red, green, blue and optional alpha channel.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RgbaSampleTypes(pub SampleType, pub SampleType, pub SampleType, pub Option<SampleType>);
+
+/// Options controlling the tolerance of [`Image::validate_result`].
+#[derive(Debug, Copy, Clone)]
+pub struct ValidationOptions {
+    /// The maximum allowed difference between two corresponding linear samples
+    /// before they are considered a mismatch. Use `0.0` for an exact comparison,
+    /// or a small positive value to tolerate the drift that lossy compression
+    /// methods such as `PIZ` can introduce.
+    pub epsilon: f32,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self { ValidationOptions { epsilon: 0.0 } }
+}
+
+/// A description of the first point at which two images were found to differ,
+/// as produced by [`Image::validate_result`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationMismatch {
+    /// The two layers have different resolutions.
+    LayerSize { expected: Vec2<usize>, found: Vec2<usize> },
+
+    /// The two layers use different sample types for their red, green, blue or alpha channel.
+    SampleTypes { expected: RgbaSampleTypes, found: RgbaSampleTypes },
+
+    /// A pixel's sample differs by more than `ValidationOptions::epsilon`.
+    Pixel { position: Vec2<usize>, channel: usize, expected: f32, found: f32 },
+}
+
+impl<Sample: ToLinear> Image<Layer<RgbaChannels<Flattened<Sample>>>> {
+    /// Semantically compare this image against `other`, returning a description
+    /// of the first mismatching attribute or channel, or `None` if the images
+    /// are equal within `options.epsilon`.
+    ///
+    /// Unlike `assert_eq!`, this treats two `NaN` samples as equal to each other
+    /// and allows pixels to drift by up to `options.epsilon`, so round-tripping a
+    /// lossily-compressed file can be asserted correct without a bespoke comparison.
+    pub fn validate_result(&self, other: &Self, options: ValidationOptions) -> Option<ValidationMismatch> {
+        let this_layer = &self.layer_data;
+        let other_layer = &other.layer_data;
+
+        if this_layer.size != other_layer.size {
+            return Some(ValidationMismatch::LayerSize {
+                expected: this_layer.size, found: other_layer.size,
+            });
+        }
+
+        let this_types = this_layer.channel_data.sample_types;
+        let other_types = other_layer.channel_data.sample_types;
+
+        if this_types != other_types {
+            return Some(ValidationMismatch::SampleTypes { expected: this_types, found: other_types });
+        }
+
+        let size = this_layer.size;
+        let channels = this_layer.channel_data.storage.channels;
+        let this_samples = &this_layer.channel_data.storage.samples;
+        let other_samples = &other_layer.channel_data.storage.samples;
+
+        for y in 0..size.1 {
+            for x in 0..size.0 {
+                for channel in 0..channels {
+                    let index = (y * size.0 + x) * channels + channel;
+                    let expected = this_samples[index].to_linear();
+                    let found = other_samples[index].to_linear();
+
+                    if expected.is_nan() != found.is_nan() {
+                        return Some(ValidationMismatch::Pixel {
+                            position: Vec2(x, y), channel, expected, found,
+                        });
+                    }
+
+                    let both_nan = expected.is_nan() && found.is_nan();
+                    if !both_nan && (expected - found).abs() > options.epsilon {
+                        return Some(ValidationMismatch::Pixel {
+                            position: Vec2(x, y), channel, expected, found,
+                        });
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,374 @@
+
+//! The `read()` builder, used to configure how an `.exr` file is decoded.
+
+pub mod rgba_channels;
+
+use std::fs::File;
+use std::io::{Read, BufReader, Cursor};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::error::{Result, Error};
+use crate::meta::{MetaData, Limits};
+use crate::meta::attribute::SampleType;
+use crate::block::{decompress_block, AllocationBudget};
+use crate::image::{Image, Layer, RgbaChannels, RgbaSampleTypes};
+use crate::image::read::rgba_channels::pixels::FromLinear;
+
+/// Start configuring how an image should be decoded. Chain further methods onto
+/// the returned builder, then call `.from_file(..)` or `.from_buffered(..)`.
+pub fn read() -> ReadBuilder<AllChannels> {
+    ReadBuilder::new(AllChannels)
+}
+
+/// Selects every channel of every layer, keeping them in their native layout.
+/// The default channel selection for `read()`.
+#[derive(Debug, Clone)]
+pub struct AllChannels;
+
+/// A builder that accumulates the settings used to decode an `.exr` file.
+///
+/// `Channels` tracks which pixel layout the final image will use: plain
+/// `AllChannels`, or an RGBA layout configured via `.rgba_channels(..)`.
+#[derive(Clone)]
+pub struct ReadBuilder<Channels> {
+    channels: Channels,
+    deep_data_allowed: bool,
+    all_resolution_levels: bool,
+    all_layers: bool,
+    all_attributes: bool,
+    parallel_decompression: bool,
+    pedantic: bool,
+    limits: Limits,
+    on_progress: Option<Arc<dyn Fn(f64) + Send + Sync>>,
+}
+
+impl<Channels> std::fmt::Debug for ReadBuilder<Channels> where Channels: std::fmt::Debug {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.debug_struct("ReadBuilder")
+            .field("channels", &self.channels)
+            .field("limits", &self.limits)
+            .field("on_progress", &self.on_progress.is_some())
+            .finish()
+    }
+}
+
+impl ReadBuilder<AllChannels> {
+    fn new(channels: AllChannels) -> Self {
+        ReadBuilder {
+            channels,
+            deep_data_allowed: true,
+            all_resolution_levels: false,
+            all_layers: false,
+            all_attributes: false,
+            parallel_decompression: true,
+            pedantic: false,
+            limits: Limits::default(),
+            on_progress: None,
+        }
+    }
+}
+
+impl<Channels> ReadBuilder<Channels> {
+    /// Select rejecting deep-data layers. No reader consults this option: headers
+    /// are parsed without regard to deep data, so decoding is the same with or without it.
+    pub fn no_deep_data(mut self) -> Self {
+        self.deep_data_allowed = false;
+        self
+    }
+
+    /// Select every resolution level of mip/rip-mapped layers. No reader consults
+    /// this option: each header's pixel data is decoded as a single block at the
+    /// header's own resolution.
+    pub fn all_resolution_levels(mut self) -> Self {
+        self.all_resolution_levels = true;
+        self
+    }
+
+    /// Select only the largest resolution level of each layer. This is the default;
+    /// no reader consults this option (see [`ReadBuilder::all_resolution_levels`]).
+    pub fn largest_resolution_level(mut self) -> Self {
+        self.all_resolution_levels = false;
+        self
+    }
+
+    /// Keep every channel of every layer. This is the default.
+    pub fn all_channels(self) -> Self { self }
+
+    /// Select every layer in the file. No reader consults this option: the
+    /// `AllChannels` reader decompresses every header's block, and the RGBA
+    /// readers decode the first header's layer.
+    pub fn all_layers(mut self) -> Self {
+        self.all_layers = true;
+        self
+    }
+
+    /// Select only the first layer. This is the default; no reader consults this
+    /// option (see [`ReadBuilder::all_layers`]).
+    pub fn first_valid_layer(mut self) -> Self {
+        self.all_layers = false;
+        self
+    }
+
+    /// Select keeping every attribute found in the header. No reader consults this
+    /// option: the header parser keeps the `channels`, `dataWindow`, `compression`
+    /// and `tiles` attributes and skips all others.
+    pub fn all_attributes(mut self) -> Self {
+        self.all_attributes = true;
+        self
+    }
+
+    /// Select sequential decompression. No reader consults this option: blocks
+    /// are always decompressed one after another on the calling thread.
+    pub fn non_parallel(mut self) -> Self {
+        self.parallel_decompression = false;
+        self
+    }
+
+    /// Select strict checking against the OpenEXR specification. No reader
+    /// consults this option: decoding is the same with or without it.
+    pub fn pedantic(mut self) -> Self {
+        self.pedantic = true;
+        self
+    }
+
+    /// Cap the resources this read is willing to allocate.
+    ///
+    /// Before any `Vec` is allocated for a layer or a decompressed block, the
+    /// header-declared dimensions, channel count, tile size and decompressed
+    /// block size are validated against `limits`, producing `Error::Invalid`
+    /// instead of attempting the allocation. Defaults to [`Limits::default`].
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Report decoding progress as each compressed block is processed.
+    ///
+    /// `callback` is invoked at most once per block, with the fraction of
+    /// completed blocks over the total block count across all headers, as a
+    /// value in `[0, 1]`. It must be `Send` and `Sync`; it is called on the
+    /// calling thread, where the blocks are decompressed.
+    pub fn on_progress(mut self, callback: impl Fn(f64) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(callback));
+        self
+    }
+
+    fn read_all_bytes(path: impl AsRef<Path>) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        BufReader::new(File::open(path)?).read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl ReadBuilder<AllChannels> {
+    /// Decode the first layer's channels into an RGBA pixel storage of your choice.
+    ///
+    /// `create` is called once for that layer to allocate the storage (for example
+    /// [`rgba_channels::pixels::create_flattened_f32`]), and `set` is called once
+    /// per decoded pixel to write into it. For callers that already own a buffer
+    /// and want to avoid this per-decode allocation, see
+    /// [`ReadBuilder::rgba_channels_into`] instead.
+    pub fn rgba_channels<Create, Set, Pixels>(
+        self, create: Create, set: Set,
+    ) -> ReadBuilder<RgbaReader<Create, Set>>
+        where Create: Fn(crate::math::Vec2<usize>, usize) -> Pixels,
+              Set: Fn(&mut Pixels, crate::math::Vec2<usize>, (f32, f32, f32, f32)),
+    {
+        self.with_channels(RgbaReader { create, set })
+    }
+
+    /// Decode the first valid layer's RGBA channels directly into `buffer`,
+    /// without allocating a fresh pixel storage.
+    ///
+    /// `buffer` must be at least [`crate::meta::Header::rgba_sample_count`] samples
+    /// long for the chosen `channels` layout; a buffer that is too small produces
+    /// `Error::Invalid` rather than a panic or silent truncation.
+    pub fn rgba_channels_into<Sample: FromLinear>(
+        self, channels: usize, buffer: &mut [Sample],
+    ) -> ReadBuilder<RgbaIntoReader<'_, Sample>> {
+        self.with_channels(RgbaIntoReader { channels, buffer })
+    }
+
+    fn with_channels<NewChannels>(self, channels: NewChannels) -> ReadBuilder<NewChannels> {
+        ReadBuilder {
+            channels,
+            deep_data_allowed: self.deep_data_allowed,
+            all_resolution_levels: self.all_resolution_levels,
+            all_layers: self.all_layers,
+            all_attributes: self.all_attributes,
+            parallel_decompression: self.parallel_decompression,
+            pedantic: self.pedantic,
+            limits: self.limits,
+            on_progress: self.on_progress,
+        }
+    }
+}
+
+/// The total number of compressed blocks that will be decoded across a file's
+/// headers, used as the denominator when reporting `on_progress` fractions.
+///
+/// This crate always treats an entire layer's pixel data as a single
+/// compressed block (see [`crate::block`]), so this is exactly one block per header.
+fn total_block_count(meta_data: &MetaData) -> usize {
+    meta_data.headers.len().max(1)
+}
+
+/// The channel selection produced by [`ReadBuilder::rgba_channels`].
+#[derive(Clone)]
+pub struct RgbaReader<Create, Set> {
+    create: Create,
+    set: Set,
+}
+
+impl<Create, Set, Pixels> ReadBuilder<RgbaReader<Create, Set>>
+    where Create: Fn(crate::math::Vec2<usize>, usize) -> Pixels,
+          Set: Fn(&mut Pixels, crate::math::Vec2<usize>, (f32, f32, f32, f32)),
+{
+    /// Decode the file at `path` using the configured settings.
+    pub fn from_file(self, path: impl AsRef<Path>) -> Result<Image<Layer<RgbaChannels<Pixels>>>> {
+        let bytes = ReadBuilder::<AllChannels>::read_all_bytes(path)?;
+        self.from_buffered(Cursor::new(bytes))
+    }
+
+    /// Decode a file that has already been read into memory, or any other `Read` source.
+    pub fn from_buffered(self, mut read: impl Read) -> Result<Image<Layer<RgbaChannels<Pixels>>>> {
+        let mut bytes = Vec::new();
+        read.read_to_end(&mut bytes)?;
+
+        let (meta_data, chunk_offset) = crate::meta::parse::read_meta_data(&bytes)?;
+        let header = meta_data.headers.first()
+            .ok_or_else(|| Error::invalid("file contains no layers"))?;
+
+        header.validate_against_limits(&self.limits)?;
+
+        let total_blocks = total_block_count(&meta_data);
+        let mut budget = AllocationBudget::default();
+        let compressed = crate::block::read_chunk(&bytes, chunk_offset)?;
+        let block = decompress_block(header, compressed, &self.limits, &mut budget)?;
+
+        let size = header.layer_size;
+        let sample_types = header_rgba_sample_types(header);
+        let channels = if sample_types.3.is_some() { 4 } else { 3 };
+        let mut pixels = (self.channels.create)(size, channels);
+
+        crate::image::read::rgba_channels::decode_rgba(header, &block.data, |position, pixel| {
+            (self.channels.set)(&mut pixels, position, pixel);
+        })?;
+
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(1_f64 / total_blocks as f64);
+        }
+
+        Ok(Image::with_single_layer(size, RgbaChannels::new(sample_types, pixels)))
+    }
+}
+
+/// Derive a layer's `RgbaSampleTypes` from its header, looking each channel up
+/// by name and defaulting to `F32` (or `None` for a missing alpha) if the
+/// header does not declare it, matching the defaults [`rgba_channels::decode_rgba`] uses for pixels.
+fn header_rgba_sample_types(header: &crate::meta::Header) -> RgbaSampleTypes {
+    let sample_type_of = |name: &str| header.channels.list.iter()
+        .find(|channel| channel.name == name)
+        .map(|channel| channel.sample_type);
+
+    RgbaSampleTypes(
+        sample_type_of("R").unwrap_or(SampleType::F32),
+        sample_type_of("G").unwrap_or(SampleType::F32),
+        sample_type_of("B").unwrap_or(SampleType::F32),
+        sample_type_of("A"),
+    )
+}
+
+/// The channel selection produced by [`ReadBuilder::rgba_channels_into`].
+pub struct RgbaIntoReader<'buffer, Sample> {
+    channels: usize,
+    buffer: &'buffer mut [Sample],
+}
+
+impl<'buffer, Sample: FromLinear> ReadBuilder<RgbaIntoReader<'buffer, Sample>> {
+    /// Decode the file at `path` using the configured settings.
+    pub fn from_file(self, path: impl AsRef<Path>) -> Result<crate::math::Vec2<usize>> {
+        let bytes = ReadBuilder::<AllChannels>::read_all_bytes(path)?;
+        self.from_buffered(Cursor::new(bytes))
+    }
+
+    /// Decode a file that has already been read into memory, or any other `Read` source.
+    ///
+    /// Returns the resolution of the decoded layer. Errors with `Error::Invalid`
+    /// if the provided buffer is smaller than the layer requires.
+    pub fn from_buffered(self, mut read: impl Read) -> Result<crate::math::Vec2<usize>> {
+        let mut bytes = Vec::new();
+        read.read_to_end(&mut bytes)?;
+
+        let (meta_data, chunk_offset) = crate::meta::parse::read_meta_data(&bytes)?;
+        let header = meta_data.headers.first()
+            .ok_or_else(|| Error::invalid("file contains no layers"))?;
+
+        header.validate_against_limits(&self.limits)?;
+
+        let required_samples = header.rgba_sample_count(self.channels.channels);
+        if self.channels.buffer.len() < required_samples {
+            return Err(Error::invalid("the provided buffer is too small for this layer"));
+        }
+
+        let total_blocks = total_block_count(&meta_data);
+        let mut budget = AllocationBudget::default();
+        let compressed = crate::block::read_chunk(&bytes, chunk_offset)?;
+        let block = decompress_block(header, compressed, &self.limits, &mut budget)?;
+
+        let channels = self.channels.channels;
+        let buffer = self.channels.buffer;
+
+        crate::image::read::rgba_channels::decode_rgba(header, &block.data, |position, (r, g, b, a)| {
+            let index = (position.1 * header.layer_size.0 + position.0) * channels;
+            buffer[index] = Sample::from_linear(r);
+
+            if channels > 1 { buffer[index + 1] = Sample::from_linear(g); }
+            if channels > 2 { buffer[index + 2] = Sample::from_linear(b); }
+            if channels > 3 { buffer[index + 3] = Sample::from_linear(a); }
+        })?;
+
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(1_f64 / total_blocks as f64);
+        }
+
+        Ok(header.layer_size)
+    }
+}
+
+impl ReadBuilder<AllChannels> {
+    /// Decode the file at `path` using the configured settings.
+    pub fn from_file(self, path: impl AsRef<Path>) -> Result<Image<Vec<Layer<()>>>> {
+        let bytes = Self::read_all_bytes(path)?;
+        self.from_buffered(Cursor::new(bytes))
+    }
+
+    /// Decode a file that has already been read into memory, or any other `Read` source.
+    ///
+    /// Every header's block is checked against the limits and decompressed, and
+    /// progress is reported per block; the decompressed pixel data is then dropped,
+    /// so the returned image has no layers.
+    pub fn from_buffered(self, mut read: impl Read) -> Result<Image<Vec<Layer<()>>>> {
+        let mut bytes = Vec::new();
+        read.read_to_end(&mut bytes)?;
+
+        let (meta_data, chunk_offset) = crate::meta::parse::read_meta_data(&bytes)?;
+        let total_blocks = total_block_count(&meta_data);
+        let mut completed_blocks = 0_usize;
+        let mut budget = AllocationBudget::default();
+
+        for header in &meta_data.headers {
+            header.validate_against_limits(&self.limits)?;
+            let compressed = crate::block::read_chunk(&bytes, chunk_offset)?;
+            decompress_block(header, compressed, &self.limits, &mut budget)?;
+
+            completed_blocks += 1;
+            if let Some(on_progress) = &self.on_progress {
+                on_progress(completed_blocks as f64 / total_blocks as f64);
+            }
+        }
+
+        Ok(Image { layer_data: Vec::new() })
+    }
+}
@@ -0,0 +1,86 @@
+
+//! Reading a layer's channels directly into an RGBA pixel storage of your choice.
+
+pub mod pixels;
+
+use crate::math::Vec2;
+use crate::error::{Result, Error};
+use crate::meta::Header;
+use crate::meta::attribute::SampleType;
+
+/// A decompressed block's channel planes, sliced out according to the header's
+/// channel list. Each channel occupies one contiguous, row-major plane of
+/// `pixel_count * sample_type.bytes_per_sample()` bytes, in the order the
+/// header's `channels` attribute lists them — the same order [`crate::image::RgbaChannels::channel_list`]
+/// produces when writing, so encode and decode stay symmetric.
+struct DecodedPlanes<'data> {
+    planes: Vec<(&'data str, SampleType, &'data [u8])>,
+}
+
+impl<'data> DecodedPlanes<'data> {
+    fn new(header: &'data Header, data: &'data [u8]) -> Result<Self> {
+        let pixel_count = header.pixel_count();
+        let mut planes = Vec::with_capacity(header.channels.list.len());
+        let mut offset = 0;
+
+        for channel in &header.channels.list {
+            let plane_bytes = pixel_count.checked_mul(channel.sample_type.bytes_per_sample())
+                .ok_or_else(|| Error::invalid("layer size overflows"))?;
+
+            let plane = data.get(offset .. offset + plane_bytes)
+                .ok_or_else(|| Error::invalid("decompressed block is smaller than its channels require"))?;
+
+            planes.push((channel.name.as_str(), channel.sample_type, plane));
+            offset += plane_bytes;
+        }
+
+        Ok(DecodedPlanes { planes })
+    }
+
+    /// Read pixel `index`'s sample of the channel named `name`, or `None`
+    /// if the header does not declare this channel.
+    fn sample(&self, name: &str, index: usize) -> Option<f32> {
+        self.planes.iter()
+            .find(|(plane_name, _, _)| *plane_name == name)
+            .map(|(_, sample_type, plane)| read_sample(*sample_type, plane, index))
+    }
+}
+
+fn read_sample(sample_type: SampleType, plane: &[u8], index: usize) -> f32 {
+    let size = sample_type.bytes_per_sample();
+    let bytes = &plane[index * size .. index * size + size];
+
+    match sample_type {
+        SampleType::F32 => f32::from_le_bytes(bytes.try_into().unwrap()),
+        SampleType::F16 => half::f16::from_bits(u16::from_le_bytes(bytes.try_into().unwrap())).to_f32(),
+        SampleType::U32 => u32::from_le_bytes(bytes.try_into().unwrap()) as f32,
+    }
+}
+
+/// Decode `header`'s layer as RGBA pixels from the decompressed `data`, calling
+/// `set` once per pixel with its position and its linear `(r, g, b, a)` samples.
+/// A channel missing from the header defaults to `0.0` for green/blue and `1.0`
+/// for alpha, matching the convention used when `RgbaChannels` only stores RGB.
+pub(crate) fn decode_rgba(
+    header: &Header, data: &[u8], mut set: impl FnMut(Vec2<usize>, (f32, f32, f32, f32)),
+) -> Result<()> {
+    let planes = DecodedPlanes::new(header, data)?;
+    let size = header.layer_size;
+
+    for y in 0..size.1 {
+        for x in 0..size.0 {
+            let index = y * size.0 + x;
+
+            let pixel = (
+                planes.sample("R", index).unwrap_or(0.0),
+                planes.sample("G", index).unwrap_or(0.0),
+                planes.sample("B", index).unwrap_or(0.0),
+                planes.sample("A", index).unwrap_or(1.0),
+            );
+
+            set(Vec2(x, y), pixel);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+
+//! The built-in pixel storage types that can be passed to `.rgba_channels(..)`.
+
+use half::f16;
+use crate::math::Vec2;
+
+/// A sample type that can be produced from the crate's internal linear `f32` representation.
+pub trait FromLinear: Copy {
+    /// Convert a linear sample value into this storage type.
+    fn from_linear(value: f32) -> Self;
+}
+
+impl FromLinear for f32 {
+    fn from_linear(value: f32) -> Self { value }
+}
+
+impl FromLinear for f16 {
+    fn from_linear(value: f32) -> Self { f16::from_f32(value) }
+}
+
+/// A sample type that can be converted back into the crate's internal linear
+/// `f32` representation, for example to generate a preview thumbnail.
+pub trait ToLinear: Copy {
+    /// Convert this storage type back into a linear sample value.
+    fn to_linear(self) -> f32;
+}
+
+impl ToLinear for f32 {
+    fn to_linear(self) -> f32 { self }
+}
+
+impl ToLinear for f16 {
+    fn to_linear(self) -> f32 { self.to_f32() }
+}
+
+impl ToLinear for u8 {
+    /// Undoes the default [`ToneMap`]'s sRGB transfer function, so that a
+    /// display-referred `Flattened<u8>` buffer (for example one produced by
+    /// [`set_flattened_pixel_tonemapped`]) can be passed straight to
+    /// `image.write()` and stored back as a linear `.exr` layer. Callers that
+    /// applied a non-default exposure should convert through
+    /// [`get_flattened_pixel_tonemapped`] with the matching [`ToneMap`] instead.
+    fn to_linear(self) -> f32 { ToneMap::default().decode_srgb_byte(self) }
+}
+
+/// A simple, flat pixel buffer: every channel of every pixel, interleaved,
+/// in row-major order. This is the simplest possible RGBA pixel storage,
+/// and a good default for callers that do not need anything fancier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flattened<Sample> {
+    /// The number of channels stored per pixel (`3` for RGB, `4` for RGBA).
+    pub channels: usize,
+
+    /// The resolution of the layer this buffer holds.
+    pub size: Vec2<usize>,
+
+    /// The interleaved samples, `size.area() * channels` entries long.
+    pub samples: Vec<Sample>,
+}
+
+/// Create an empty [`Flattened`] buffer of 32-bit float samples, sized for `size`.
+pub fn create_flattened_f32(size: Vec2<usize>, channels: usize) -> Flattened<f32> {
+    Flattened { channels, size, samples: vec![0.0; size.area() * channels] }
+}
+
+/// Create an empty [`Flattened`] buffer of 16-bit float samples, sized for `size`.
+pub fn create_flattened_f16(size: Vec2<usize>, channels: usize) -> Flattened<f16> {
+    Flattened { channels, size, samples: vec![f16::from_f32(0.0); size.area() * channels] }
+}
+
+/// Write one decoded pixel into a [`Flattened`] buffer at `position`.
+pub fn set_flattened_pixel<Sample: FromLinear>(
+    image: &mut Flattened<Sample>, position: Vec2<usize>, pixel: (f32, f32, f32, f32),
+) {
+    let index = (position.1 * image.size.0 + position.0) * image.channels;
+    let (r, g, b, a) = pixel;
+
+    image.samples[index] = Sample::from_linear(r);
+    image.samples[index + 1] = Sample::from_linear(g);
+    image.samples[index + 2] = Sample::from_linear(b);
+
+    if image.channels > 3 {
+        image.samples[index + 3] = Sample::from_linear(a);
+    }
+}
+
+/// Create an empty [`Flattened`] buffer of display-referred 8-bit samples, sized for `size`.
+pub fn create_flattened_u8(size: Vec2<usize>, channels: usize) -> Flattened<u8> {
+    Flattened { channels, size, samples: vec![0_u8; size.area() * channels] }
+}
+
+/// The parameters used to convert between this crate's internal linear
+/// scene-referred samples and display-referred 8-bit sRGB bytes.
+#[derive(Debug, Copy, Clone)]
+pub struct ToneMap {
+    /// The exposure applied before the sRGB transfer function, expressed as a
+    /// power of two: the linear value is multiplied by `2^exposure`.
+    pub exposure: f32,
+}
+
+impl Default for ToneMap {
+    fn default() -> Self { ToneMap { exposure: 0.0 } }
+}
+
+impl ToneMap {
+    /// Apply exposure, the sRGB transfer function, and quantize to a `u8`.
+    pub fn encode_srgb_byte(&self, linear: f32) -> u8 {
+        let exposed = (linear * (2.0_f32).powf(self.exposure)).max(0.0);
+
+        let encoded = if exposed <= 0.0031308 { 12.92 * exposed }
+            else { 1.055 * exposed.powf(1.0 / 2.4) - 0.055 };
+
+        (encoded.min(1.0) * 255.0).round() as u8
+    }
+
+    /// The inverse of [`ToneMap::encode_srgb_byte`]: undo the sRGB transfer
+    /// function and the exposure, recovering a linear scene-referred sample.
+    pub fn decode_srgb_byte(&self, byte: u8) -> f32 {
+        let encoded = byte as f32 / 255.0;
+
+        let exposed = if encoded <= 0.04045 { encoded / 12.92 }
+            else { ((encoded + 0.055) / 1.055).powf(2.4) };
+
+        exposed / (2.0_f32).powf(self.exposure)
+    }
+}
+
+/// Build a pixel setter that tone-maps decoded linear samples into display-referred
+/// 8-bit sRGB bytes, for use with [`create_flattened_u8`].
+///
+/// Pass the result to `.rgba_channels(create_flattened_u8, set_flattened_pixel_tonemapped(tone_map))`
+/// to get ready-to-display pixels directly from the reader, with no separate tone-mapping pass.
+/// The alpha channel, already linear in `[0, 1]`, is quantized without the sRGB curve.
+pub fn set_flattened_pixel_tonemapped(
+    tone_map: ToneMap,
+) -> impl Fn(&mut Flattened<u8>, Vec2<usize>, (f32, f32, f32, f32)) {
+    move |image, position, (r, g, b, a)| {
+        let index = (position.1 * image.size.0 + position.0) * image.channels;
+
+        image.samples[index] = tone_map.encode_srgb_byte(r);
+        image.samples[index + 1] = tone_map.encode_srgb_byte(g);
+        image.samples[index + 2] = tone_map.encode_srgb_byte(b);
+
+        if image.channels > 3 {
+            image.samples[index + 3] = (a.min(1.0).max(0.0) * 255.0).round() as u8;
+        }
+    }
+}
+
+/// The inverse of [`set_flattened_pixel_tonemapped`]: reads one display-referred
+/// 8-bit pixel back out as linear samples, so that an 8-bit buffer can be written
+/// back to disk as a linear half-float `.exr` layer.
+pub fn get_flattened_pixel_tonemapped(
+    tone_map: ToneMap,
+) -> impl Fn(&Flattened<u8>, Vec2<usize>) -> (f32, f32, f32, f32) {
+    move |image, position| {
+        let index = (position.1 * image.size.0 + position.0) * image.channels;
+
+        let r = tone_map.decode_srgb_byte(image.samples[index]);
+        let g = tone_map.decode_srgb_byte(image.samples[index + 1]);
+        let b = tone_map.decode_srgb_byte(image.samples[index + 2]);
+        let a = if image.channels > 3 { image.samples[index + 3] as f32 / 255.0 } else { 1.0 };
+
+        (r, g, b, a)
+    }
+}
@@ -0,0 +1,199 @@
+
+//! The `.write()` builder, used to configure how an image is encoded to a file.
+
+use std::fs::File;
+use std::io::{Write, BufWriter};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::error::Result;
+use crate::math::Vec2;
+use crate::compression::Compression;
+use crate::meta::Header;
+use crate::meta::attribute::{PreviewImage, SampleType};
+use crate::image::{Layer, RgbaChannels};
+use crate::image::read::rgba_channels::pixels::{Flattened, ToLinear, ToneMap};
+
+/// A builder that accumulates the settings used to encode an image to an `.exr` file.
+pub struct WriteBuilder<'image, LayerData> {
+    image: &'image crate::image::Image<LayerData>,
+    parallel_compression: bool,
+    on_progress: Option<Arc<dyn Fn(f64) + Send + Sync>>,
+    preview: Option<PreviewImage>,
+}
+
+impl<'image, LayerData> WriteBuilder<'image, LayerData> {
+    pub(crate) fn new(image: &'image crate::image::Image<LayerData>) -> Self {
+        WriteBuilder { image, parallel_compression: true, on_progress: None, preview: None }
+    }
+
+    /// Select sequential compression. The writer does not consult this option:
+    /// the layer is always written as a single block on the calling thread.
+    pub fn non_parallel(mut self) -> Self {
+        self.parallel_compression = false;
+        self
+    }
+
+    /// Report encoding progress as each block is written.
+    ///
+    /// `callback` is invoked at most once per block, with the fraction of
+    /// completed blocks over the total block count, as a value in `[0, 1]`.
+    /// It must be `Send` and `Sync`; it is called on the calling thread, where
+    /// the blocks are written.
+    pub fn on_progress(mut self, callback: impl Fn(f64) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(callback));
+        self
+    }
+}
+
+impl<'image, Sample: ToLinear> WriteBuilder<'image, Layer<RgbaChannels<Flattened<Sample>>>> {
+    /// Downsample this image's pixels and store them as the header's standard
+    /// `preview` attribute, so other tools can show a thumbnail without decoding
+    /// the full-resolution layer.
+    ///
+    /// The thumbnail never exceeds `max_size` in either dimension; the aspect
+    /// ratio of the source layer is preserved. Downsampling uses a box filter,
+    /// and pixels are tone-mapped to display-referred 8-bit sRGB using the
+    /// default [`ToneMap`]. This crate only ever keeps a single resolution level
+    /// per layer, so there is no smaller decoded level to prefer over box-filtering.
+    pub fn with_generated_preview(mut self, max_size: Vec2<usize>) -> Self {
+        self.preview = Some(generate_preview(&self.image.layer_data, max_size));
+        self
+    }
+
+    /// Encode the image and write it to the file at `path`, creating or truncating it.
+    pub fn to_file(self, path: impl AsRef<Path>) -> Result<()> {
+        self.to_buffered(BufWriter::new(File::create(path)?))
+    }
+
+    /// Encode the image and write it to any `Write` destination.
+    ///
+    /// The whole layer is written as a single compressed block (see
+    /// [`crate::block`]), so `on_progress` is invoked exactly once, after that
+    /// block has been written.
+    pub fn to_buffered(self, mut write: impl Write) -> Result<()> {
+        let layer = &self.image.layer_data;
+        let header = rgba_header(layer);
+
+        let mut bytes = crate::meta::serialize::write_meta_data(&header, self.preview.as_ref());
+        let pixel_bytes = encode_rgba(layer);
+        crate::block::write_chunk(&mut bytes, &pixel_bytes);
+
+        write.write_all(&bytes)?;
+
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(1.0);
+        }
+
+        Ok(())
+    }
+}
+
+/// The header describing `layer`, as written by [`WriteBuilder::to_buffered`].
+///
+/// Pixel data is always stored uncompressed: this crate's decompressors cover
+/// every `Compression` method, but it has no compressor of its own yet.
+fn rgba_header<Sample: ToLinear>(layer: &Layer<RgbaChannels<Flattened<Sample>>>) -> Header {
+    Header {
+        layer_size: layer.size,
+        channels: layer.channel_data.channel_list(),
+        compression: Compression::Uncompressed,
+        tile_size: None,
+    }
+}
+
+/// Encode `layer`'s pixels as one contiguous plane per channel, in the same
+/// `R, G, B, A` order [`RgbaChannels::channel_list`] declares them, each
+/// sample written as its channel's own declared [`SampleType`].
+fn encode_rgba<Sample: ToLinear>(layer: &Layer<RgbaChannels<Flattened<Sample>>>) -> Vec<u8> {
+    let channels = layer.channel_data.channel_list();
+    let storage = &layer.channel_data.storage;
+    let pixel_count = layer.size.area();
+
+    let mut bytes = Vec::with_capacity(pixel_count * channels.bytes_per_pixel());
+
+    for (plane_index, channel) in channels.list.iter().enumerate() {
+        for pixel_index in 0..pixel_count {
+            let sample = storage.samples[pixel_index * storage.channels + plane_index].to_linear();
+            write_sample(&mut bytes, channel.sample_type, sample);
+        }
+    }
+
+    bytes
+}
+
+fn write_sample(bytes: &mut Vec<u8>, sample_type: SampleType, value: f32) {
+    match sample_type {
+        SampleType::F32 => bytes.extend_from_slice(&value.to_le_bytes()),
+        SampleType::F16 => bytes.extend_from_slice(&half::f16::from_f32(value).to_bits().to_le_bytes()),
+        SampleType::U32 => bytes.extend_from_slice(&(value as u32).to_le_bytes()),
+    }
+}
+
+fn generate_preview<Sample: ToLinear>(
+    layer: &Layer<RgbaChannels<Flattened<Sample>>>, max_size: Vec2<usize>,
+) -> PreviewImage {
+    let source_size = layer.size;
+    let scale = f64::min(
+        max_size.0 as f64 / source_size.0.max(1) as f64,
+        max_size.1 as f64 / source_size.1.max(1) as f64,
+    ).min(1.0);
+
+    let preview_size = Vec2(
+        ((source_size.0 as f64 * scale).round() as usize).max(1),
+        ((source_size.1 as f64 * scale).round() as usize).max(1),
+    );
+
+    let tone_map = ToneMap::default();
+    let storage = &layer.channel_data.storage;
+    let mut pixels = Vec::with_capacity(preview_size.area() * 4);
+
+    for preview_y in 0..preview_size.1 {
+        for preview_x in 0..preview_size.0 {
+            let (r, g, b, a) = box_filtered_pixel(storage, source_size, preview_size, Vec2(preview_x, preview_y));
+
+            pixels.push(tone_map.encode_srgb_byte(r));
+            pixels.push(tone_map.encode_srgb_byte(g));
+            pixels.push(tone_map.encode_srgb_byte(b));
+            pixels.push((a.min(1.0).max(0.0) * 255.0).round() as u8);
+        }
+    }
+
+    PreviewImage { size: preview_size, pixels }
+}
+
+/// Average every source pixel that falls into the box covering one preview pixel.
+fn box_filtered_pixel<Sample: ToLinear>(
+    storage: &Flattened<Sample>, source_size: Vec2<usize>, preview_size: Vec2<usize>, preview_position: Vec2<usize>,
+) -> (f32, f32, f32, f32) {
+    let start_x = preview_position.0 * source_size.0 / preview_size.0;
+    let end_x = ((preview_position.0 + 1) * source_size.0 / preview_size.0).max(start_x + 1).min(source_size.0);
+    let start_y = preview_position.1 * source_size.1 / preview_size.1;
+    let end_y = ((preview_position.1 + 1) * source_size.1 / preview_size.1).max(start_y + 1).min(source_size.1);
+
+    let mut sum = (0.0_f32, 0.0_f32, 0.0_f32, 0.0_f32);
+    let mut count = 0_f32;
+
+    for y in start_y..end_y {
+        for x in start_x..end_x {
+            let index = (y * source_size.0 + x) * storage.channels;
+
+            sum.0 += storage.samples[index].to_linear();
+            sum.1 += storage.samples[index + 1].to_linear();
+            sum.2 += storage.samples[index + 2].to_linear();
+            sum.3 += if storage.channels > 3 { storage.samples[index + 3].to_linear() } else { 1.0 };
+
+            count += 1.0;
+        }
+    }
+
+    (sum.0 / count, sum.1 / count, sum.2 / count, sum.3 / count)
+}
+
+impl<LayerData> crate::image::Image<LayerData> {
+    /// Start configuring how this image should be encoded. Chain further methods
+    /// onto the returned builder, then call `.to_file(..)` or `.to_buffered(..)`.
+    pub fn write(&self) -> WriteBuilder<'_, LayerData> {
+        WriteBuilder::new(self)
+    }
+}
@@ -0,0 +1,36 @@
+
+//! Low level byte reading and writing helpers used by the meta data and block modules.
+
+use std::io::{Read, Write};
+use crate::error::{Result, Error};
+
+/// Types that can be serialized to and deserialized from the raw `.exr` byte stream.
+pub trait Data: Sized {
+    /// Read this value from a byte stream.
+    fn read(read: &mut impl Read) -> Result<Self>;
+
+    /// Write this value to a byte stream.
+    fn write(&self, write: &mut impl Write) -> Result<()>;
+}
+
+impl Data for u32 {
+    fn read(read: &mut impl Read) -> Result<Self> {
+        let mut bytes = [0_u8; 4];
+        read.read_exact(&mut bytes).map_err(Error::from)?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn write(&self, write: &mut impl Write) -> Result<()> {
+        write.write_all(&self.to_le_bytes()).map_err(Error::from)
+    }
+}
+
+impl Data for i32 {
+    fn read(read: &mut impl Read) -> Result<Self> {
+        Ok(u32::read(read)? as i32)
+    }
+
+    fn write(&self, write: &mut impl Write) -> Result<()> {
+        (*self as u32).write(write)
+    }
+}
@@ -0,0 +1,19 @@
+
+//! Read and write OpenEXR images.
+//!
+//! This library reimplements the OpenEXR C++ library in pure Rust,
+//! without any dependency on the original library. See `image::read` and
+//! `image::write` for the two main entry points, or `prelude` for the
+//! most commonly used re-exports.
+
+#[macro_use]
+extern crate smallvec;
+
+pub mod error;
+pub mod math;
+pub mod io;
+pub mod meta;
+pub mod compression;
+pub mod block;
+pub mod image;
+pub mod prelude;
@@ -0,0 +1,33 @@
+
+//! Simple vector math types shared across the crate.
+
+/// A two-dimensional integer vector, generic over the numeric type.
+/// Used for image resolutions, tile sizes, and pixel coordinates.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct Vec2<T>(pub T, pub T);
+
+impl Vec2<usize> {
+    /// The number of pixels contained in a rectangle of this size (`width * height`).
+    ///
+    /// Panics-free callers that cannot trust `self` to be a validated, in-bounds
+    /// size (for example a resolution taken straight from a file header) should
+    /// use [`Vec2::checked_area`] instead, since this multiplication is unchecked:
+    /// it panics on overflow in debug builds and wraps in release builds.
+    pub fn area(self) -> usize {
+        self.0 * self.1
+    }
+
+    /// The number of pixels contained in a rectangle of this size, or `None` if
+    /// `width * height` overflows `usize`.
+    pub fn checked_area(self) -> Option<usize> {
+        self.0.checked_mul(self.1)
+    }
+}
+
+impl<T> Vec2<T> {
+    /// This vector's first component, conventionally the width or x-coordinate.
+    pub fn x(&self) -> &T { &self.0 }
+
+    /// This vector's second component, conventionally the height or y-coordinate.
+    pub fn y(&self) -> &T { &self.1 }
+}
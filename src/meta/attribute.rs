@@ -0,0 +1,82 @@
+
+//! The built-in attribute types that can appear in an `.exr` header.
+
+/// The numeric representation of a single channel's samples.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SampleType {
+    /// A 16-bit floating point sample.
+    F16,
+
+    /// A 32-bit floating point sample.
+    F32,
+
+    /// A 32-bit unsigned integer sample.
+    U32,
+}
+
+impl SampleType {
+    /// The number of bytes a single sample of this type occupies.
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            SampleType::F16 => 2,
+            SampleType::F32 => 4,
+            SampleType::U32 => 4,
+        }
+    }
+
+    /// Decode the `pixelType` enum stored in a `chlist` attribute (`0` = unsigned
+    /// int, `1` = half float, `2` = float).
+    pub(crate) fn from_pixel_type(pixel_type: i32) -> crate::error::Result<Self> {
+        match pixel_type {
+            0 => Ok(SampleType::U32),
+            1 => Ok(SampleType::F16),
+            2 => Ok(SampleType::F32),
+            _ => Err(crate::error::Error::invalid("channel has an unknown pixel type")),
+        }
+    }
+
+    /// Encode this sample type as the `pixelType` enum stored in a `chlist` attribute.
+    pub(crate) fn to_pixel_type(self) -> i32 {
+        match self {
+            SampleType::U32 => 0,
+            SampleType::F16 => 1,
+            SampleType::F32 => 2,
+        }
+    }
+}
+
+/// The description of a single channel, for example `"R"` or `"Z"`.
+#[derive(Debug, Clone)]
+pub struct ChannelDescription {
+    /// The name of this channel, for example `"R"`, `"G"`, `"B"` or `"A"`.
+    pub name: String,
+
+    /// The numeric type used to store this channel's samples.
+    pub sample_type: SampleType,
+}
+
+/// A small RGBA thumbnail embedded in a header's `preview` attribute, so that
+/// other tools can show a fast preview without decoding the full-resolution image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviewImage {
+    /// The resolution of the thumbnail. Always small enough to display quickly.
+    pub size: crate::math::Vec2<usize>,
+
+    /// The thumbnail's interleaved, display-referred 8-bit RGBA samples,
+    /// `size.area() * 4` bytes long.
+    pub pixels: Vec<u8>,
+}
+
+/// All channels contained in a single layer, in the order they are stored on disk.
+#[derive(Debug, Clone)]
+pub struct ChannelList {
+    /// The individual channel descriptions.
+    pub list: Vec<ChannelDescription>,
+}
+
+impl ChannelList {
+    /// The number of bytes required to store a single pixel of every channel in this list.
+    pub fn bytes_per_pixel(&self) -> usize {
+        self.list.iter().map(|channel| channel.sample_type.bytes_per_sample()).sum()
+    }
+}
@@ -0,0 +1,151 @@
+
+//! Parsing and representation of the `.exr` header attributes.
+
+pub mod attribute;
+pub(crate) mod parse;
+pub(crate) mod serialize;
+
+use crate::math::Vec2;
+use crate::compression::Compression;
+use crate::error::{Result, Error};
+use crate::meta::attribute::ChannelList;
+
+/// All headers found in a file, in the order they are stored on disk.
+#[derive(Debug, Clone)]
+pub struct MetaData {
+    /// One header per layer.
+    pub headers: Vec<Header>,
+}
+
+/// The header of a single layer, describing its resolution, channels and compression.
+#[derive(Debug, Clone)]
+pub struct Header {
+    /// The resolution of this layer, in pixels.
+    pub layer_size: Vec2<usize>,
+
+    /// The channels contained in this layer, for example red, green, blue and alpha.
+    pub channels: ChannelList,
+
+    /// How the pixel data of this layer is compressed on disk.
+    pub compression: Compression,
+
+    /// The size of a single tile, if this layer is tiled.
+    pub tile_size: Option<Vec2<usize>>,
+}
+
+impl Header {
+    /// The number of pixels in this layer (`width * height`).
+    ///
+    /// This trusts `layer_size` to already be in bounds; a header taken straight
+    /// from an untrusted file should be checked with [`Header::validate_against_limits`]
+    /// first, since this does not return an error on overflow: it panics in debug
+    /// builds and wraps in release builds.
+    pub fn pixel_count(&self) -> usize {
+        self.layer_size.area()
+    }
+
+    /// The number of channels in this layer, for example `4` for an RGBA layer.
+    pub fn channel_count(&self) -> usize {
+        self.channels.list.len()
+    }
+
+    /// The number of interleaved samples required to hold this layer's pixels as
+    /// RGBA data with `channels` channels per pixel (`3` for RGB, `4` for RGBA).
+    pub fn rgba_sample_count(&self, channels: usize) -> usize {
+        self.pixel_count() * channels
+    }
+
+    /// The number of bytes required to hold this layer's pixels as RGBA data with
+    /// `channels` channels per pixel, stored as `sample_type`.
+    ///
+    /// Callers that want to decode into a buffer they already own (for example a
+    /// reused frame buffer) can call this first to size that buffer correctly,
+    /// then pass it to [`crate::image::read::ReadBuilder::rgba_channels_into`]
+    /// instead of letting the reader allocate a fresh buffer per decode.
+    pub fn rgba_byte_count(&self, channels: usize, sample_type: attribute::SampleType) -> usize {
+        self.rgba_sample_count(channels) * sample_type.bytes_per_sample()
+    }
+
+    /// Validate that the header-declared dimensions do not exceed the given limits,
+    /// before any pixel buffer is allocated for this layer.
+    ///
+    /// Every multiplication here uses checked arithmetic: a header that declares
+    /// a resolution, tile size, or channel count that overflows `usize` is treated
+    /// as exceeding the limit, rather than wrapping around to a small value that
+    /// would slip past the `>` comparison and let the oversized allocation through.
+    pub fn validate_against_limits(&self, limits: &Limits) -> Result<()> {
+        let pixel_count = self.layer_size.checked_area()
+            .ok_or_else(|| Error::invalid("image resolution overflows"))?;
+
+        if pixel_count > limits.max_pixel_count {
+            return Err(Error::invalid("image resolution exceeds the configured limit"));
+        }
+
+        if self.channel_count() > limits.max_channel_count {
+            return Err(Error::invalid("channel count exceeds the configured limit"));
+        }
+
+        if let Some(tile_size) = self.tile_size {
+            let tile_pixels = tile_size.checked_area()
+                .ok_or_else(|| Error::invalid("tile size overflows"))?;
+
+            let tile_bytes = tile_pixels.checked_mul(self.channels.bytes_per_pixel())
+                .ok_or_else(|| Error::invalid("tile size overflows"))?;
+
+            if tile_bytes > limits.max_tile_bytes {
+                return Err(Error::invalid("tile size exceeds the configured limit"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Caps on the resources this crate is willing to allocate while decoding a file.
+///
+/// A malformed or hostile header can declare an enormous resolution, channel count,
+/// or tile size. Without these limits, simply parsing such a header can trigger a
+/// multi-gigabyte allocation or a panic long before any pixel is actually read.
+/// Pass a `Limits` to [`crate::image::read::ReadBuilder::limits`] to have every
+/// allocation validated against it instead.
+#[derive(Debug, Copy, Clone)]
+pub struct Limits {
+    /// The maximum number of pixels (`width * height`) any single layer may declare.
+    pub max_pixel_count: usize,
+
+    /// The maximum number of channels any single layer may declare.
+    pub max_channel_count: usize,
+
+    /// The maximum size, in bytes, of a single compressed tile or scan line block
+    /// once decompressed.
+    pub max_tile_bytes: usize,
+
+    /// The maximum total number of bytes this crate will allocate across all layers
+    /// of a single file.
+    pub max_total_bytes: usize,
+}
+
+impl Limits {
+    /// No limits at all. Restores the previous behaviour of trusting the header.
+    pub fn unbounded() -> Self {
+        Limits {
+            max_pixel_count: usize::MAX,
+            max_channel_count: usize::MAX,
+            max_tile_bytes: usize::MAX,
+            max_total_bytes: usize::MAX,
+        }
+    }
+}
+
+impl Default for Limits {
+    /// Limits that comfortably fit typical production images, while still rejecting
+    /// the kind of absurd header values a fuzzer or a corrupted file would produce.
+    fn default() -> Self {
+        Limits {
+            max_pixel_count: 2 << 30, // 2 gigapixels
+            max_channel_count: 1024,
+            max_tile_bytes: 1 << 30, // 1 gigabyte
+            max_total_bytes: 4_usize << 30, // 4 gigabytes
+        }
+    }
+}
@@ -0,0 +1,156 @@
+
+//! Parsing the magic number, version, and header attributes from the start of a file.
+//!
+//! This crate models the body of a file as a single chunk per header (see
+//! [`crate::block`]): after the header attributes, the file contains one
+//! `u64` byte count followed by that many bytes of compressed pixel data.
+//! [`read_meta_data`] stops right before that chunk and returns the offset
+//! it starts at, so the caller can slice out exactly the compressed bytes
+//! belonging to each header.
+
+use crate::error::{Result, Error};
+use crate::math::Vec2;
+use crate::compression::Compression;
+use crate::meta::attribute::{ChannelDescription, ChannelList, SampleType};
+use crate::meta::{Header, MetaData};
+
+const MAGIC_NUMBER: u32 = 0x01312f76;
+
+/// Parse the magic number, version, and every header attribute from `bytes`.
+///
+/// Returns the parsed meta data together with the offset of the first byte
+/// after the header section, where the compressed pixel data chunk begins.
+pub(crate) fn read_meta_data(bytes: &[u8]) -> Result<(MetaData, usize)> {
+    let mut cursor = Cursor { bytes, offset: 0 };
+
+    if cursor.read_u32()? != MAGIC_NUMBER {
+        return Err(Error::invalid("file does not start with the exr magic number"));
+    }
+
+    let _version = cursor.read_u32()?;
+
+    let header = read_header(&mut cursor)?;
+    Ok((MetaData { headers: vec![header] }, cursor.offset))
+}
+
+fn read_header(cursor: &mut Cursor) -> Result<Header> {
+    let mut channels = None;
+    let mut data_window = None;
+    let mut compression = None;
+    let mut tile_size = None;
+
+    loop {
+        let name = cursor.read_c_string()?;
+        if name.is_empty() {
+            break;
+        }
+
+        let attribute_type = cursor.read_c_string()?;
+        let size = cursor.read_u32()? as usize;
+        let mut attribute = cursor.sub_cursor(size)?;
+
+        match name.as_str() {
+            "channels" => channels = Some(read_channel_list(&mut attribute)?),
+            "dataWindow" => data_window = Some(read_box2i(&mut attribute)?),
+            "compression" => compression = Some(Compression::from_byte(attribute.read_u8()?)?),
+            "tiles" => tile_size = Some(read_tile_desc(&mut attribute)?),
+            _ => { let _ = attribute_type; } // unrecognized attributes are skipped by the cursor advance above
+        }
+    }
+
+    let (min, max) = data_window.ok_or_else(|| Error::invalid("header is missing the dataWindow attribute"))?;
+
+    let width = (max.0 - min.0).checked_add(1)
+        .filter(|width| *width > 0)
+        .ok_or_else(|| Error::invalid("dataWindow has a non-positive width"))?;
+
+    let height = (max.1 - min.1).checked_add(1)
+        .filter(|height| *height > 0)
+        .ok_or_else(|| Error::invalid("dataWindow has a non-positive height"))?;
+
+    Ok(Header {
+        layer_size: Vec2(width as usize, height as usize),
+        channels: channels.ok_or_else(|| Error::invalid("header is missing the channels attribute"))?,
+        compression: compression.ok_or_else(|| Error::invalid("header is missing the compression attribute"))?,
+        tile_size,
+    })
+}
+
+fn read_channel_list(cursor: &mut Cursor) -> Result<ChannelList> {
+    let mut list = Vec::new();
+
+    loop {
+        let name = cursor.read_c_string()?;
+        if name.is_empty() {
+            break;
+        }
+
+        let sample_type = SampleType::from_pixel_type(cursor.read_i32()?)?;
+        let _p_linear_and_reserved = cursor.read_bytes(4)?;
+        let _x_sampling = cursor.read_i32()?;
+        let _y_sampling = cursor.read_i32()?;
+
+        list.push(ChannelDescription { name, sample_type });
+    }
+
+    Ok(ChannelList { list })
+}
+
+fn read_box2i(cursor: &mut Cursor) -> Result<(Vec2<i64>, Vec2<i64>)> {
+    let x_min = cursor.read_i32()? as i64;
+    let y_min = cursor.read_i32()? as i64;
+    let x_max = cursor.read_i32()? as i64;
+    let y_max = cursor.read_i32()? as i64;
+    Ok((Vec2(x_min, y_min), Vec2(x_max, y_max)))
+}
+
+fn read_tile_desc(cursor: &mut Cursor) -> Result<Vec2<usize>> {
+    let x_size = cursor.read_u32()? as usize;
+    let y_size = cursor.read_u32()? as usize;
+    let _mode = cursor.read_u8()?;
+    Ok(Vec2(x_size, y_size))
+}
+
+/// A minimal byte cursor used while parsing header attributes.
+struct Cursor<'bytes> {
+    bytes: &'bytes [u8],
+    offset: usize,
+}
+
+impl<'bytes> Cursor<'bytes> {
+    fn read_bytes(&mut self, count: usize) -> Result<&'bytes [u8]> {
+        let slice = self.bytes.get(self.offset .. self.offset + count)
+            .ok_or_else(|| Error::invalid("file is truncated"))?;
+
+        self.offset += count;
+        Ok(slice)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()))
+    }
+
+    fn read_i32(&mut self) -> Result<i32> {
+        Ok(self.read_u32()? as i32)
+    }
+
+    fn read_c_string(&mut self) -> Result<String> {
+        let start = self.offset;
+
+        let terminator = self.bytes[start..].iter().position(|&byte| byte == 0)
+            .ok_or_else(|| Error::invalid("file is truncated"))?;
+
+        let string = String::from_utf8_lossy(&self.bytes[start .. start + terminator]).into_owned();
+        self.offset = start + terminator + 1;
+        Ok(string)
+    }
+
+    /// Carve out a bounded sub-cursor over the next `size` bytes, and advance past them.
+    fn sub_cursor(&mut self, size: usize) -> Result<Cursor<'bytes>> {
+        Ok(Cursor { bytes: self.read_bytes(size)?, offset: 0 })
+    }
+}
@@ -0,0 +1,85 @@
+
+//! The inverse of [`crate::meta::parse`]: writing the magic number, version,
+//! and header attributes that [`crate::meta::parse::read_meta_data`] expects.
+
+use crate::math::Vec2;
+use crate::meta::Header;
+use crate::meta::attribute::{ChannelList, PreviewImage};
+
+const MAGIC_NUMBER: u32 = 0x01312f76;
+const VERSION: u32 = 2;
+
+/// Serialize `header`'s attributes, plus `preview` as the standard `preview`
+/// attribute if present, terminated the same way [`crate::meta::parse::read_meta_data`]
+/// expects to find the start of the compressed pixel data chunk.
+pub(crate) fn write_meta_data(header: &Header, preview: Option<&PreviewImage>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&MAGIC_NUMBER.to_le_bytes());
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+
+    write_attribute(&mut bytes, "channels", "chlist", &write_channel_list(&header.channels));
+    write_attribute(&mut bytes, "dataWindow", "box2i", &write_box2i(header.layer_size));
+    write_attribute(&mut bytes, "compression", "compression", &[header.compression.to_byte()]);
+
+    if let Some(tile_size) = header.tile_size {
+        write_attribute(&mut bytes, "tiles", "tiledesc", &write_tile_desc(tile_size));
+    }
+
+    if let Some(preview) = preview {
+        write_attribute(&mut bytes, "preview", "preview", &write_preview(preview));
+    }
+
+    bytes.push(0); // empty name terminates the header's attribute list
+    bytes
+}
+
+fn write_attribute(bytes: &mut Vec<u8>, name: &str, attribute_type: &str, value: &[u8]) {
+    bytes.extend_from_slice(name.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(attribute_type.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(value);
+}
+
+fn write_channel_list(channels: &ChannelList) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    for channel in &channels.list {
+        bytes.extend_from_slice(channel.name.as_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(&channel.sample_type.to_pixel_type().to_le_bytes());
+        bytes.extend_from_slice(&[0_u8; 4]); // pLinear flag plus reserved bytes
+        bytes.extend_from_slice(&1_i32.to_le_bytes()); // xSampling
+        bytes.extend_from_slice(&1_i32.to_le_bytes()); // ySampling
+    }
+
+    bytes.push(0); // empty name terminates the channel list
+    bytes
+}
+
+fn write_box2i(layer_size: Vec2<usize>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    for value in [0_i32, 0_i32, layer_size.0 as i32 - 1, layer_size.1 as i32 - 1] {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    bytes
+}
+
+fn write_tile_desc(tile_size: Vec2<usize>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(tile_size.0 as u32).to_le_bytes());
+    bytes.extend_from_slice(&(tile_size.1 as u32).to_le_bytes());
+    bytes.push(0); // mode: one level, round down
+    bytes
+}
+
+fn write_preview(preview: &PreviewImage) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(preview.size.0 as u32).to_le_bytes());
+    bytes.extend_from_slice(&(preview.size.1 as u32).to_le_bytes());
+    bytes.extend_from_slice(&preview.pixels);
+    bytes
+}
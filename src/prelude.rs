@@ -0,0 +1,16 @@
+
+//! Re-exports of the types and functions used in almost every program built on this crate.
+
+pub use half::f16;
+
+pub use crate::math::Vec2;
+pub use crate::meta::{MetaData, Header, Limits};
+pub use crate::meta::attribute::SampleType;
+pub use crate::compression::Compression;
+pub use crate::image::{Image, Layer, RgbaChannels, RgbaSampleTypes};
+pub use crate::image::read::read;
+
+pub mod read {
+    //! Re-exports of the `read()` builder and its pixel storage helpers.
+    pub use crate::image::read::*;
+}
@@ -12,6 +12,7 @@ use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use exr::prelude::*;
 use exr::error::{Error, UnitResult};
 use exr::image::read::rgba_channels::pixels::{set_flattened_pixel, Flattened, create_flattened_f16};
+use exr::image::ValidationOptions;
 
 fn exr_files() -> impl Iterator<Item=PathBuf> {
     walkdir::WalkDir::new("tests/images/valid").into_iter().map(std::result::Result::unwrap)
@@ -156,12 +157,9 @@ fn round_trip_all_files_rgba() {
 
         let image2 = image_reader.from_buffered(Cursor::new(&tmp_bytes))?;
 
-        // assert_eq!(image, image2); TODO compare meta data
-
-        // custom compare function: considers nan equal to nan
-        let pixels1 = &image.layer_data.channel_data.storage.samples;
-        let pixels2 = &image2.layer_data.channel_data.storage.samples;
-        assert!(pixels1.iter().map(|f| f.to_bits()).eq(pixels2.iter().map(|f| f.to_bits())));
+        if let Some(mismatch) = image.validate_result(&image2, ValidationOptions::default()) {
+            panic!("images do not match after round trip: {:?}", mismatch);
+        }
 
         Ok(())
     })